@@ -0,0 +1,26 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Command-line options accepted by RuBen (bin/ruben.rs) to configure a Benchmarker run.
+
+use structopt::StructOpt;
+
+/// Command line options for running RuBen, the Libra Benchmarker binary.
+#[derive(Clone, Debug, StructOpt)]
+pub struct BenchOpt {
+    /// InfluxDB HTTP endpoint (e.g. http://localhost:8086) to stream metrics to.
+    /// Leave unset to disable metrics streaming; Benchmarker behaves exactly as before.
+    #[structopt(long = "influxdb-endpoint")]
+    pub influxdb_endpoint: Option<String>,
+
+    /// InfluxDB database name metrics are written into.
+    #[structopt(long = "influxdb-database", default_value = "benchmark")]
+    pub influxdb_database: String,
+
+    /// Max number of TXNs batched into a single pipelined burst per account before awaiting
+    /// responses from AdmissionControlClient. The default of 1 submits requests one at a time,
+    /// which differs from the original (pre-batching) whole-chunk submission and may trade
+    /// away some submission throughput; raise this to batch more TXNs per round trip.
+    #[structopt(long = "batch-size", default_value = "1")]
+    pub batch_size: usize,
+}
@@ -0,0 +1,148 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! End-to-end commit latency tracking.
+//!
+//! `Benchmarker` measures throughput but never the latency any individual TXN experiences.
+//! `SubmitTimeCache` is a TTL-bounded map from `(AccountAddress, sequence_number)` to the
+//! `Instant` a TXN was submitted, analogous to the `metrics_cache: TtlCache<(AccountAddress,
+//! u64), SystemTime>` used by mempool. Once a sender's synchronized sequence number advances
+//! past a recorded entry, the gap between submit and commit is the TXN's latency; entries for
+//! TXNs that are rejected (and so never reach a matching committed sequence number) simply age
+//! out of the cache rather than being looked up.
+
+use libra_types::account_address::AccountAddress;
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// TTL-bounded map from (sender, expected sequence number) to the Instant a TXN was submitted.
+pub struct SubmitTimeCache {
+    ttl: Duration,
+    entries: HashMap<(AccountAddress, u64), Instant>,
+}
+
+impl SubmitTimeCache {
+    pub fn new(ttl: Duration) -> Self {
+        SubmitTimeCache {
+            ttl,
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Record that `sender`'s TXN with `sequence_number` was submitted at `submitted_at`.
+    pub fn insert(&mut self, sender: AccountAddress, sequence_number: u64, submitted_at: Instant) {
+        self.entries.insert((sender, sequence_number), submitted_at);
+    }
+
+    /// Remove and return the submit Instant for (sender, sequence_number), unless it has
+    /// already aged out of the TTL window.
+    pub fn take(&mut self, sender: AccountAddress, sequence_number: u64) -> Option<Instant> {
+        let submitted_at = self.entries.remove(&(sender, sequence_number))?;
+        if submitted_at.elapsed() > self.ttl {
+            None
+        } else {
+            Some(submitted_at)
+        }
+    }
+
+    /// Drop entries that have aged out, e.g. TXNs that were rejected and will never have a
+    /// matching committed sequence number.
+    pub fn evict_expired(&mut self) {
+        let ttl = self.ttl;
+        self.entries
+            .retain(|_, submitted_at| submitted_at.elapsed() <= ttl);
+    }
+}
+
+/// p50/p90/p99/max end-to-end commit latency, in milliseconds, over some set of TXNs.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LatencyStats {
+    pub p50_ms: u128,
+    pub p90_ms: u128,
+    pub p99_ms: u128,
+    pub max_ms: u128,
+}
+
+/// Compute percentile latency stats from a set of per-TXN latencies.
+/// Entries whose submit timestamp aged out of the `SubmitTimeCache` are skipped by the
+/// caller before this is invoked, so `latencies_ms` only holds attributable samples.
+pub fn compute_percentiles(mut latencies_ms: Vec<u128>) -> LatencyStats {
+    if latencies_ms.is_empty() {
+        return LatencyStats::default();
+    }
+    latencies_ms.sort_unstable();
+    // Nearest-rank percentile: the smallest sample with at least p of all samples at or below it.
+    let percentile = |p: f64| -> u128 {
+        let rank = (latencies_ms.len() as f64 * p).ceil() as usize;
+        latencies_ms[std::cmp::min(std::cmp::max(rank, 1), latencies_ms.len()) - 1]
+    };
+    LatencyStats {
+        p50_ms: percentile(0.50),
+        p90_ms: percentile(0.90),
+        p99_ms: percentile(0.99),
+        max_ms: *latencies_ms.last().expect("latencies_ms is non-empty"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libra_types::account_address::AccountAddress;
+    use std::thread;
+
+    #[test]
+    fn empty_latencies_yield_default_stats() {
+        let stats = compute_percentiles(vec![]);
+        assert_eq!(stats.p50_ms, 0);
+        assert_eq!(stats.p90_ms, 0);
+        assert_eq!(stats.p99_ms, 0);
+        assert_eq!(stats.max_ms, 0);
+    }
+
+    #[test]
+    fn single_latency_is_every_percentile() {
+        let stats = compute_percentiles(vec![42]);
+        assert_eq!(stats.p50_ms, 42);
+        assert_eq!(stats.p90_ms, 42);
+        assert_eq!(stats.p99_ms, 42);
+        assert_eq!(stats.max_ms, 42);
+    }
+
+    #[test]
+    fn percentiles_are_computed_over_sorted_latencies() {
+        let latencies_ms: Vec<u128> = (1..=100).collect();
+        let stats = compute_percentiles(latencies_ms);
+        assert_eq!(stats.p50_ms, 50);
+        assert_eq!(stats.p90_ms, 90);
+        assert_eq!(stats.p99_ms, 99);
+        assert_eq!(stats.max_ms, 100);
+    }
+
+    #[test]
+    fn take_returns_none_for_unknown_entry() {
+        let mut cache = SubmitTimeCache::new(Duration::from_secs(60));
+        assert!(cache.take(AccountAddress::default(), 0).is_none());
+    }
+
+    #[test]
+    fn take_returns_none_after_ttl_expires() {
+        let mut cache = SubmitTimeCache::new(Duration::from_millis(1));
+        cache.insert(AccountAddress::default(), 0, Instant::now());
+        thread::sleep(Duration::from_millis(10));
+        assert!(cache.take(AccountAddress::default(), 0).is_none());
+    }
+
+    #[test]
+    fn evict_expired_drops_only_aged_out_entries() {
+        let mut cache = SubmitTimeCache::new(Duration::from_millis(10));
+        let sender = AccountAddress::default();
+        cache.insert(sender, 0, Instant::now());
+        thread::sleep(Duration::from_millis(20));
+        cache.insert(sender, 1, Instant::now());
+        cache.evict_expired();
+        assert!(cache.take(sender, 0).is_none());
+        assert!(cache.take(sender, 1).is_some());
+    }
+}
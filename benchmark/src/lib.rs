@@ -1,10 +1,7 @@
 // Copyright (c) The Libra Core Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use admission_control_proto::proto::{
-    admission_control::AdmissionControlClient,
-    admission_control::SubmitTransactionResponse as ProtoSubmitTransactionResponse,
-};
+use admission_control_proto::proto::admission_control::AdmissionControlClient;
 use client::{AccountData, AccountStatus};
 use crypto::{ed25519::*, test_utils::KeyPair};
 use generate_keypair::load_key_from_file;
@@ -13,23 +10,69 @@ use libra_types::{account_address::AccountAddress, account_config::association_a
 use logger::prelude::*;
 use metrics::OpMetrics;
 use rand::Rng;
-use std::{collections::HashMap, convert::TryInto, sync::Arc, thread, time};
+use std::{
+    collections::{HashMap, HashSet},
+    convert::TryInto,
+    sync::{Arc, Mutex},
+    thread, time,
+};
 
 pub mod bin_utils;
 pub mod cli_opt;
 pub mod grpc_helpers;
+pub mod latency;
 pub mod load_generator;
+pub mod metrics_sink;
 pub mod submit_rate;
+pub mod tps_sampler;
 
 use grpc_helpers::{
     divide_items, get_account_states, submit_and_wait_requests, sync_account_sequence_number,
 };
+use latency::{LatencyStats, SubmitTimeCache};
 use load_generator::Request;
+use metrics_sink::{InfluxSink, MetricPoint};
+use tps_sampler::{TpsSample, TpsSampler};
 
 lazy_static! {
     pub static ref OP_COUNTER: OpMetrics = OpMetrics::new_and_registered("benchmark");
 }
 
+/// Interval at which each client's background thread polls for the latest committed
+/// ledger version while a round of requests is being submitted and waited on.
+const TPS_SAMPLE_INTERVAL_MS: u64 = 100;
+
+/// How long a submitted TXN's timestamp is kept around waiting for a matching committed
+/// sequence number before it's considered aged out (e.g. the TXN was rejected downstream).
+const SUBMIT_TIME_CACHE_TTL: time::Duration = time::Duration::from_secs(60);
+
+/// How long a TXN may sit in `run_sustained_load`'s outstanding window without committing
+/// before it's aged out of the backlog estimate, so a handful of stuck TXNs can't convince
+/// the rate controller the whole validator has stalled.
+const MAX_TX_QUEUE_AGE_MS: u128 = 30_000;
+
+/// Outstanding-queue size, in seconds of offered load at the current submit rate, above which
+/// `run_sustained_load`'s rate controller backs off instead of ramping up.
+const SUSTAINED_LOAD_BACKLOG_THRESHOLD_SECS: f64 = 2.0;
+
+/// Rejection ratio above which `run_sustained_load`'s rate controller backs off.
+const SUSTAINED_LOAD_REJECTION_THRESHOLD: f64 = 0.05;
+
+/// Multiplicative up/down steps `run_sustained_load` applies to the submit rate each tick.
+const SUSTAINED_LOAD_RATE_UP_STEP: f64 = 1.1;
+const SUSTAINED_LOAD_RATE_DOWN_STEP: f64 = 0.8;
+
+/// Max number of resubmit rounds for TXNs that are still uncommitted after
+/// `wait_txns_committed`, before giving up and declaring them timed out.
+const MAX_RETRY_COUNT: u32 = 3;
+
+/// Wall-clock budget across all retries within one
+/// `submit_requests_and_wait_txns_committed_with_retry` call.
+const RETRY_TTL: time::Duration = time::Duration::from_secs(30);
+
+/// How often the optional InfluxDB sink flushes accumulated metric points.
+const METRICS_SINK_FLUSH_INTERVAL: time::Duration = time::Duration::from_secs(10);
+
 /// Benchmark library for Libra Blockchain.
 ///
 /// Benchmarker aims to automate the process of submitting requests to admission control
@@ -47,8 +90,10 @@ lazy_static! {
 ///     submit_txns.failure.ac.{ac_status_code}, submit_txns.failure.mempool.{mempool_status_code},
 ///     submit_txns.failure.vm..{vm_status}, submit_txns.{grpc_error}, submit_read_requests.{error};
 ///   * Final status within epoch: committed_txns, timedout_txns;
-/// * Gauges: request_duration_ms, running_duration_ms, request_throughput, txns_throughput.
-/// * Histograms: read_requests.response_bytes.
+/// * Gauges: submit_duration_ms, wait_duration_ms, running_duration_ms, request_throughput,
+///   txn_throughput,
+///   max_txn_throughput (sampled via tps_sampler instead of derived from before/after counts).
+/// * Histograms: read_requests.response_bytes, e2e_txn_latency_ms.
 pub struct Benchmarker {
     /// Using multiple clients can help improve the request speed.
     clients: Vec<Arc<AdmissionControlClient>>,
@@ -59,6 +104,21 @@ pub struct Benchmarker {
     prev_sequence_numbers: HashMap<AccountAddress, u64>,
     /// Submit requests with specified rate. Minting opearation always floods requests.
     submit_rate: u64,
+    /// Submit Instant of each in-flight TXN, keyed by (sender, expected sequence number),
+    /// used to attribute end-to-end commit latency once a sender's sequence number advances.
+    submit_time_cache: Arc<Mutex<SubmitTimeCache>>,
+    /// Identifier tagging every metric point this Benchmarker streams to `metrics_sink`.
+    run_id: String,
+    /// Optional sink streaming metrics to InfluxDB; a no-op unless configured via
+    /// `with_metrics_sink`.
+    metrics_sink: InfluxSink,
+    /// Max number of requests grouped into a single pipelined burst to AdmissionControlClient
+    /// before awaiting responses. Note this is a behavior change from the pre-batching code,
+    /// which always submitted an entire per-client chunk in one `submit_and_wait_requests`
+    /// call: 1 (the default) instead submits one request at a time, trading submission
+    /// throughput for more precise per-request latency attribution. Raise `batch_size` to
+    /// amortize round trips back toward the original throughput.
+    batch_size: usize,
 }
 
 /// Summary of the results of playing TXNs with Benchmarker.
@@ -74,6 +134,31 @@ pub struct BenchSummary {
     submit_duration_ms: u128,
     /// Duration to wait TXNs committed.
     wait_duration_ms: u128,
+    /// Highest incremental TPS observed by the per-client ledger version samplers.
+    max_txn_throughput: f64,
+    /// Raw (time, committed ledger version) samples collected during this round, one
+    /// sequence per client.
+    tps_samples: Vec<TpsSample>,
+    /// End-to-end commit latency percentiles for TXNs committed during this round.
+    latency_stats: LatencyStats,
+    /// Number of resubmit rounds performed to recover TXNs that were still
+    /// uncommitted after the initial wait. Always 0 unless the `_with_retry` API was used.
+    retry_attempts: u32,
+    /// Of the TXNs uncommitted after the initial wait, how many eventually committed after
+    /// being resubmitted.
+    committed_after_retry: usize,
+}
+
+/// Summary of a `run_sustained_load` run: a long window of closed-loop offered load that
+/// converges toward (rather than bursts at) a target TPS.
+#[derive(Debug)]
+pub struct SustainedLoadSummary {
+    /// Steady-state committed TXNs per second, averaged over the whole run.
+    pub committed_tps: f64,
+    /// Submit rate the controller converged to by the end of the run.
+    pub converged_submit_rate: u64,
+    /// Fraction of submitted TXNs that were rejected by AC over the run.
+    pub rejection_ratio: f64,
 }
 
 impl BenchSummary {
@@ -111,6 +196,37 @@ impl BenchSummary {
     pub fn has_uncommitted_txns(&self) -> bool {
         self.num_accepted - self.num_committed > 0
     }
+
+    /// Highest incremental TPS observed between any two consecutive ledger version samples,
+    /// as opposed to `txn_throughput`'s flat average over the whole round.
+    pub fn max_txn_throughput(&self) -> f64 {
+        self.max_txn_throughput
+    }
+
+    /// Raw ledger version samples collected while this round was running, one sequence per
+    /// client, for callers that want to plot a throughput curve themselves.
+    pub fn tps_samples(&self) -> &[TpsSample] {
+        &self.tps_samples
+    }
+
+    /// p50/p90/p99/max end-to-end commit latency, in milliseconds, for TXNs committed
+    /// during this round. TXNs whose submit timestamp aged out of the submit time cache
+    /// before they committed are excluded.
+    pub fn latency_stats(&self) -> LatencyStats {
+        self.latency_stats
+    }
+
+    /// Number of resubmit rounds performed by
+    /// `submit_requests_and_wait_txns_committed_with_retry`. Always 0 otherwise.
+    pub fn retry_attempts(&self) -> u32 {
+        self.retry_attempts
+    }
+
+    /// Of the TXNs uncommitted after the initial wait, how many eventually committed after
+    /// being resubmitted.
+    pub fn committed_after_retry(&self) -> usize {
+        self.committed_after_retry
+    }
 }
 
 impl Benchmarker {
@@ -130,9 +246,37 @@ impl Benchmarker {
             stagger_range_ms,
             prev_sequence_numbers,
             submit_rate,
+            submit_time_cache: Arc::new(Mutex::new(SubmitTimeCache::new(SUBMIT_TIME_CACHE_TTL))),
+            run_id: "default".to_string(),
+            metrics_sink: InfluxSink::new(None, String::new(), METRICS_SINK_FLUSH_INTERVAL),
+            batch_size: 1,
         }
     }
 
+    /// Stream metrics to the InfluxDB endpoint configured in `opt`, tagging every point with
+    /// `run_id` and this Benchmarker's client count. A no-op if `opt.influxdb_endpoint` is
+    /// unset, leaving existing local-only behavior unchanged.
+    pub fn with_metrics_sink(mut self, opt: &cli_opt::BenchOpt, run_id: String) -> Self {
+        self.metrics_sink = InfluxSink::new(
+            opt.influxdb_endpoint.clone(),
+            opt.influxdb_database.clone(),
+            METRICS_SINK_FLUSH_INTERVAL,
+        );
+        self.run_id = run_id;
+        self
+    }
+
+    /// Group up to `batch_size` requests into a single pipelined burst to
+    /// AdmissionControlClient before awaiting responses. This is a behavior change from the
+    /// pre-batching code, which always submitted an entire per-client chunk in one call: 1
+    /// (the default) now submits one request at a time instead, which may trade away some
+    /// submission throughput for finer-grained latency attribution. Raise `batch_size` to
+    /// recover the original whole-chunk submission behavior.
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = std::cmp::max(1, batch_size);
+        self
+    }
+
     /// -------------------------------------------------------------------- ///
     ///  Benchmark setup: Load faucet account and minting APIs and helpers.  ///
     /// -------------------------------------------------------------------- ///
@@ -175,14 +319,16 @@ impl Benchmarker {
 
     /// Minting given accounts using self's AC client(s).
     /// Mint TXNs must be 100% successful in order to continue benchmark.
-    /// Therefore mint_accounts() will panic when any mint TXN is not accepted or fails.
+    /// A brief AC/mempool hiccup is recovered from by resubmitting the still-outstanding mint
+    /// TXNs (see submit_requests_and_wait_txns_committed_with_retry); mint_accounts() only
+    /// panics once that retry budget is exhausted.
     /// Known issue: Minting opereations from two different Benchmarker instances
     /// will fail because they are sharing the same faucet account.
     pub fn mint_accounts(&mut self, mint_requests: &[Request], faucet_account: &mut AccountData) {
         // Disable client staggering for mint operations.
         let stagger_range_ms = self.stagger_range_ms;
         self.stagger_range_ms = 1;
-        let result = self.submit_requests_and_wait_txns_committed(
+        let result = self.submit_requests_and_wait_txns_committed_with_retry(
             mint_requests,
             std::slice::from_mut(faucet_account),
             Some(std::u64::MAX), /* Flood minting TXNs. */
@@ -191,10 +337,12 @@ impl Benchmarker {
         // We stop immediately if any minting fails.
         if result.has_rejected_txns() || result.has_uncommitted_txns() {
             panic!(
-                "{} of {} mint transaction(s) accepted, and {} failed",
+                "{} of {} mint transaction(s) accepted, and {} failed after {} retr{}",
                 result.num_accepted,
                 mint_requests.len(),
                 result.num_accepted - result.num_committed,
+                result.retry_attempts,
+                if result.retry_attempts == 1 { "y" } else { "ies" },
             )
         }
     }
@@ -214,9 +362,17 @@ impl Benchmarker {
     }
 
     /// Send both TXNs and read requests to AC async, wait for TXNs' responses from AC.
-    /// Read requests are handled in a separate thread.
-    /// Return #accepted TXNs and submission duration.
-    pub fn submit_requests(&mut self, requests: &[Request], submit_rate: u64) -> (usize, u128) {
+    /// Read requests are handled in a separate thread. Requests are grouped into batches of
+    /// up to `self.batch_size` before each is awaited, per-TXN accounting is unaffected.
+    /// Return the accepted TXNs, identified by (sender, sequence number) rather than just a
+    /// count, so callers that submit the same TXN more than once (see
+    /// `submit_requests_and_wait_txns_committed_with_retry`) can dedup acceptance by identity
+    /// instead of double-counting a duplicate accept. Also returns submission duration.
+    fn submit_requests_with_accepted_keys(
+        &mut self,
+        requests: &[Request],
+        submit_rate: u64,
+    ) -> (HashSet<(AccountAddress, u64)>, u128) {
         let req_chunks = divide_items(requests, self.clients.len());
         let now = time::Instant::now();
         // Zip req_chunks with clients: when first iter returns none,
@@ -227,34 +383,64 @@ impl Benchmarker {
                 let local_chunk = Vec::from(chunk);
                 let local_client = Arc::clone(client);
                 let stagger_range_ms = self.stagger_range_ms;
+                let local_submit_time_cache = Arc::clone(&self.submit_time_cache);
+                let batch_size = self.batch_size;
                 // Spawn threads with corresponding client.
                 thread::spawn(
-                    // Dispatch requests to client and submit, return the list of responses
-                    // that are accepted by AC, and how long the client is delayed.
-                    move || -> (Vec<ProtoSubmitTransactionResponse>, u16) {
+                    // Dispatch requests to client and submit, return the (sender, sequence
+                    // number) of every TXN accepted by AC, and how long the client is delayed.
+                    move || -> (HashSet<(AccountAddress, u64)>, u16) {
                         let delay_duration_ms = Self::stagger_client(stagger_range_ms);
                         debug!(
                             "Dispatch {} requests to client after staggered {} ms.",
                             local_chunk.len(),
                             delay_duration_ms,
                         );
-                        (
-                            submit_and_wait_requests(&local_client, local_chunk, submit_rate),
-                            delay_duration_ms,
-                        )
+                        // Group up to `batch_size` requests into a single pipelined burst
+                        // before awaiting responses, the way light-client transaction
+                        // propagation caps MAX_TRANSACTIONS_TO_PROPAGATE, so the measured
+                        // submission-rate ceiling rises without flooding AC unbounded.
+                        let mut accepted_keys = HashSet::new();
+                        for batch in local_chunk.chunks(batch_size) {
+                            // Record the submit Instant per batch, right before it's actually
+                            // dispatched, so a request's measured latency doesn't include time
+                            // spent waiting its turn behind earlier batches in this chunk.
+                            let submit_instant = time::Instant::now();
+                            {
+                                let mut submit_time_cache = local_submit_time_cache
+                                    .lock()
+                                    .expect("submit time cache lock poisoned");
+                                for request in batch {
+                                    submit_time_cache.insert(
+                                        request.sender,
+                                        request.sequence_number,
+                                        submit_instant,
+                                    );
+                                }
+                            }
+                            // submit_and_wait_requests returns exactly the (request, response)
+                            // pairs AC accepted, which is what lets us attribute acceptance to
+                            // a specific TXN rather than only a blind count.
+                            for (request, _response) in
+                                submit_and_wait_requests(&local_client, batch.to_vec(), submit_rate)
+                            {
+                                accepted_keys.insert((request.sender, request.sequence_number));
+                            }
+                        }
+                        (accepted_keys, delay_duration_ms)
                     },
                 )
             })
             .collect();
-        // Wait for threads and gather reponses.
+        // Wait for threads and gather accepted keys.
         // TODO: Group response by error type and report staticstics.
-        let mut txn_resps: Vec<ProtoSubmitTransactionResponse> = vec![];
+        let mut accepted_keys: HashSet<(AccountAddress, u64)> = HashSet::new();
         let mut delay_duration_ms = self.stagger_range_ms;
         for child in children {
-            let resp_tuple = child.join().expect("failed to join a request thread");
-            txn_resps.extend(resp_tuple.0.into_iter());
+            let (chunk_accepted_keys, delay) = child.join().expect("failed to join a request thread");
+            accepted_keys.extend(chunk_accepted_keys);
             // Start counting time as soon as the first client starts to submit requests.
-            delay_duration_ms = std::cmp::min(delay_duration_ms, resp_tuple.1);
+            delay_duration_ms = std::cmp::min(delay_duration_ms, delay);
         }
         let mut request_duration_ms = now.elapsed().as_millis();
         // Calling stagger_client() should ensure delay duration strictly < self.stagger_range_ms.
@@ -263,10 +449,18 @@ impl Benchmarker {
         }
         info!(
             "Submitted and accepted {} TXNs within {} ms.",
-            txn_resps.len(),
+            accepted_keys.len(),
             request_duration_ms,
         );
-        (txn_resps.len(), request_duration_ms)
+        (accepted_keys, request_duration_ms)
+    }
+
+    /// Send both TXNs and read requests to AC async, wait for TXNs' responses from AC.
+    /// Return #accepted TXNs and submission duration.
+    pub fn submit_requests(&mut self, requests: &[Request], submit_rate: u64) -> (usize, u128) {
+        let (accepted_keys, submit_duration_ms) =
+            self.submit_requests_with_accepted_keys(requests, submit_rate);
+        (accepted_keys.len(), submit_duration_ms)
     }
 
     /// Wait for accepted TXNs to commit or time out: for any account, if its sequence number
@@ -313,7 +507,7 @@ impl Benchmarker {
     /// With the previous stored sequence number (e.g. self.prev_sequence_numbers)
     /// and the synchronized sequence number from validator, calculate how many TXNs are committed.
     /// Update both senders sequence numbers and self.prev_sequence_numbers to the just-queried
-    /// synchrnized sequence numbers. Return (#committed, #uncommitted) TXNs.
+    /// synchrnized sequence numbers. Return (#committed, #uncommitted, per-TXN latencies) TXNs.
     /// Reason to backtrace sender's sequence number:
     /// If some of sender's TXNs are not committed because they are rejected by AC,
     /// we should use the synchronized sequence number in future TXN generation.
@@ -324,9 +518,16 @@ impl Benchmarker {
         &mut self,
         senders: &mut [AccountData],
         sync_sequence_numbers: &HashMap<AccountAddress, u64>,
-    ) -> (usize, usize) {
+    ) -> (usize, usize, Vec<u128>) {
         let mut committed_txns = 0;
         let mut uncommitted_txns = 0;
+        let mut latencies_ms: Vec<u128> = vec![];
+        // Drop submit timestamps for TXNs that will never commit (e.g. rejected downstream) so
+        // the cache doesn't grow unbounded over a long-running sustained load.
+        self.submit_time_cache
+            .lock()
+            .expect("submit time cache lock poisoned")
+            .evict_expired();
         // Invariant for any account X in Benchmarker:
         // 1) X's current persisted sequence number (X.sequence_number) >=
         //    X's synchronized sequence number (sync_sequence_number[X])
@@ -349,6 +550,24 @@ impl Benchmarker {
                     sender.sequence_number - *sync_sequence_number
                 );
             }
+            // Attribute a latency to every sequence number that just became committed. TXNs
+            // whose submit timestamp already aged out of the cache (or was never recorded)
+            // are skipped rather than treated as a measurement of 0.
+            {
+                let mut submit_time_cache = self
+                    .submit_time_cache
+                    .lock()
+                    .expect("submit time cache lock poisoned");
+                for committed_sequence_number in *prev_sequence_number..*sync_sequence_number {
+                    if let Some(submit_instant) =
+                        submit_time_cache.take(sender.address, committed_sequence_number)
+                    {
+                        let latency_ms = submit_instant.elapsed().as_millis();
+                        OP_COUNTER.observe("e2e_txn_latency_ms", latency_ms as f64);
+                        latencies_ms.push(latency_ms);
+                    }
+                }
+            }
             committed_txns += *sync_sequence_number - *prev_sequence_number;
             uncommitted_txns += sender.sequence_number - *sync_sequence_number;
             *prev_sequence_number = *sync_sequence_number;
@@ -366,7 +585,44 @@ impl Benchmarker {
             .expect("Unable to convert u64 to usize");
         OP_COUNTER.inc_by("committed_txns", committed_txns_usize);
         OP_COUNTER.inc_by("timedout_txns", uncommitted_txns_usize);
-        (committed_txns_usize, uncommitted_txns_usize)
+        (committed_txns_usize, uncommitted_txns_usize, latencies_ms)
+    }
+
+    /// Submit one round of `requests`, wait for commits, and return this round's accepted
+    /// TXNs (by identity, not just a count), committed count, submit/wait durations, TPS
+    /// samples, and per-TXN latencies. Shared by `submit_requests_and_wait_txns_committed`
+    /// and its `_with_retry` variant, which needs every round's accepted keys -- not just a
+    /// count -- to track acceptance by TXN identity across retries.
+    fn submit_round(
+        &mut self,
+        requests: &[Request],
+        senders: &mut [AccountData],
+        rate: u64,
+    ) -> (HashSet<(AccountAddress, u64)>, usize, u128, u128, Vec<TpsSample>, Vec<u128>) {
+        // Start one sampler per client before submitting so ramp-up is captured, and stop them
+        // only after we're done waiting for TXNs to commit.
+        let samplers: Vec<TpsSampler> = self
+            .clients
+            .iter()
+            .map(|client| TpsSampler::spawn(Arc::clone(client), TPS_SAMPLE_INTERVAL_MS))
+            .collect();
+        let (accepted_keys, submit_duration_ms) =
+            self.submit_requests_with_accepted_keys(requests, rate);
+        let (sync_sequence_numbers, wait_duration_ms) = self.wait_txns_committed(senders);
+        let (num_committed, _, latencies_ms) =
+            self.check_txn_results(senders, &sync_sequence_numbers);
+        let tps_samples: Vec<TpsSample> = samplers
+            .into_iter()
+            .flat_map(TpsSampler::stop_and_join)
+            .collect();
+        (
+            accepted_keys,
+            num_committed,
+            submit_duration_ms,
+            wait_duration_ms,
+            tps_samples,
+            latencies_ms,
+        )
     }
 
     /// Implement the general way to submit requests to Libra and then
@@ -379,15 +635,127 @@ impl Benchmarker {
         submit_rate: Option<u64>,
     ) -> BenchSummary {
         let rate = submit_rate.unwrap_or(self.submit_rate);
-        let (num_accepted, submit_duration_ms) = self.submit_requests(requests, rate);
-        let (sync_sequence_numbers, wait_duration_ms) = self.wait_txns_committed(senders);
-        let (num_committed, _) = self.check_txn_results(senders, &sync_sequence_numbers);
+        let (accepted_keys, num_committed, submit_duration_ms, wait_duration_ms, tps_samples, latencies_ms) =
+            self.submit_round(requests, senders, rate);
+        let max_txn_throughput = tps_sampler::compute_stats(&tps_samples).max_tps;
+        let latency_stats = latency::compute_percentiles(latencies_ms);
         BenchSummary {
             num_submitted: requests.len(),
-            num_accepted,
+            num_accepted: accepted_keys.len(),
             num_committed,
             submit_duration_ms,
             wait_duration_ms,
+            max_txn_throughput,
+            tps_samples,
+            latency_stats,
+            retry_attempts: 0,
+            committed_after_retry: 0,
+        }
+    }
+
+    /// Like `submit_requests_and_wait_txns_committed`, but a brief validator/mempool hiccup
+    /// doesn't immediately count as failure: for any account still trailing its local
+    /// sequence number after waiting, resubmit exactly the still-outstanding TXNs from
+    /// `requests` (found by matching sender and sequence number against the gap), up to
+    /// `MAX_RETRY_COUNT` rounds or until `RETRY_TTL` elapses, before declaring the remainder
+    /// timed out.
+    ///
+    /// The retry filter necessarily resubmits some TXNs that were already accepted in an
+    /// earlier round but are simply still pending commit, not just genuinely-rejected ones.
+    /// Accepted TXNs are therefore tracked by (sender, sequence number) identity in a set
+    /// across every round rather than summed as a blind per-round count, so a duplicate
+    /// accept response for an already-accepted TXN can't inflate the total past
+    /// `num_submitted` (and can't make `has_uncommitted_txns`' subtraction underflow).
+    pub fn submit_requests_and_wait_txns_committed_with_retry(
+        &mut self,
+        requests: &[Request],
+        senders: &mut [AccountData],
+        submit_rate: Option<u64>,
+    ) -> BenchSummary {
+        let rate = submit_rate.unwrap_or(self.submit_rate);
+        // Each sender's highest sequence number among its original requests; one past it is
+        // the locally expected sequence number once every one of them lands, used to restore
+        // AccountData after check_txn_results resets it down to the synced (committed-so-far)
+        // value.
+        let sender_targets = max_sequence_numbers(requests);
+
+        let (mut accepted_keys, mut num_committed, submit_duration_ms, wait_duration_ms, mut tps_samples, mut latencies_ms) =
+            self.submit_round(requests, senders, rate);
+
+        let retry_deadline = time::Instant::now() + RETRY_TTL;
+        let mut attempts = 0u32;
+        let mut committed_after_retry = 0usize;
+        while accepted_keys.len() > num_committed {
+            if attempts >= MAX_RETRY_COUNT || time::Instant::now() >= retry_deadline {
+                warn!(
+                    "Giving up on {} uncommitted TXN(s) after {} retries.",
+                    accepted_keys.len() - num_committed,
+                    attempts,
+                );
+                break;
+            }
+            attempts += 1;
+            // The sender's sequence_number was reset to the synced value by check_txn_results,
+            // so anything in `requests` with a sequence_number >= that is still outstanding.
+            let retry_requests: Vec<Request> = requests
+                .iter()
+                .filter(|request| {
+                    senders
+                        .iter()
+                        .find(|sender| sender.address == request.sender)
+                        .map_or(false, |sender| request.sequence_number >= sender.sequence_number)
+                })
+                .cloned()
+                .collect();
+            if retry_requests.is_empty() {
+                break;
+            }
+            if attempts >= MAX_RETRY_COUNT || time::Instant::now() >= retry_deadline {
+                warn!(
+                    "Giving up on {} uncommitted TXN(s) after {} retries.",
+                    retry_requests.len(),
+                    attempts,
+                );
+                break;
+            }
+            attempts += 1;
+            info!(
+                "Retrying {} timed-out TXN(s), attempt {} of {}.",
+                retry_requests.len(),
+                attempts,
+                MAX_RETRY_COUNT,
+            );
+            // Restore each retried sender's locally expected sequence number before resubmitting,
+            // so check_txn_results' invariants hold once these land.
+            for sender in senders.iter_mut() {
+                if let Some(target) = sender_targets.get(&sender.address) {
+                    sender.sequence_number = *target + 1;
+                }
+            }
+            let (retry_accepted_keys, retry_num_committed, _, _, retry_tps_samples, retry_latencies_ms) =
+                self.submit_round(&retry_requests, senders, rate);
+            committed_after_retry += retry_num_committed;
+            num_committed += retry_num_committed;
+            // HashSet::extend dedups by (sender, sequence number): a duplicate accept for a
+            // TXN already accepted in a previous round is a no-op here instead of inflating
+            // the total past num_submitted.
+            accepted_keys.extend(retry_accepted_keys);
+            tps_samples.extend(retry_tps_samples);
+            latencies_ms.extend(retry_latencies_ms);
+        }
+        let max_txn_throughput = tps_sampler::compute_stats(&tps_samples).max_tps;
+        let latency_stats = latency::compute_percentiles(latencies_ms);
+        BenchSummary {
+            num_submitted: requests.len(),
+            num_accepted: accepted_keys.len(),
+            num_committed,
+            submit_duration_ms,
+            wait_duration_ms,
+            max_txn_throughput,
+            tps_samples,
+            latency_stats,
+            retry_attempts: attempts,
+            committed_after_retry,
         }
     }
 
@@ -416,6 +784,310 @@ impl Benchmarker {
         OP_COUNTER.set("running_duration_ms", result.running_duration_ms() as usize);
         OP_COUNTER.set("request_throughput", result.req_throughput() as usize);
         OP_COUNTER.set("txn_throughput", result.txn_throughput() as usize);
+        OP_COUNTER.set("max_txn_throughput", result.max_txn_throughput() as usize);
+        let latency_stats = result.latency_stats();
+        OP_COUNTER.set("e2e_latency_p50_ms", latency_stats.p50_ms as usize);
+        OP_COUNTER.set("e2e_latency_p90_ms", latency_stats.p90_ms as usize);
+        OP_COUNTER.set("e2e_latency_p99_ms", latency_stats.p99_ms as usize);
+        OP_COUNTER.set("e2e_latency_max_ms", latency_stats.max_ms as usize);
+        self.metrics_sink.submit(MetricPoint::new(
+            "benchmark",
+            vec![
+                ("run_id".to_string(), self.run_id.clone()),
+                ("num_clients".to_string(), self.clients.len().to_string()),
+            ],
+            vec![
+                ("request_throughput".to_string(), result.req_throughput()),
+                ("txn_throughput".to_string(), result.txn_throughput()),
+                (
+                    "committed_txns".to_string(),
+                    result.num_committed as f64,
+                ),
+                (
+                    "timedout_txns".to_string(),
+                    (result.num_accepted - result.num_committed) as f64,
+                ),
+                ("e2e_latency_p50_ms".to_string(), latency_stats.p50_ms as f64),
+                ("e2e_latency_p90_ms".to_string(), latency_stats.p90_ms as f64),
+                ("e2e_latency_p99_ms".to_string(), latency_stats.p99_ms as f64),
+                (
+                    "submit_duration_ms".to_string(),
+                    result.submit_duration_ms as f64,
+                ),
+                ("wait_duration_ms".to_string(), result.wait_duration_ms as f64),
+            ],
+        ));
         result
     }
+
+    /// ------------------------------------------------------------------- ///
+    ///  Sustained, closed-loop load with adaptive rate towards target_tps.  ///
+    /// ------------------------------------------------------------------- ///
+
+    /// Play through `requests` for up to `duration`, adapting the submit rate towards
+    /// `target_tps` instead of flooding one fixed batch. Unlike `measure_txn_throughput`,
+    /// which plays one burst and stops, this keeps a steady offered load over a long window
+    /// so callers can find the max sustainable throughput rather than a burst number.
+    /// Stops early if `requests` is exhausted before `duration` elapses.
+    pub fn run_sustained_load(
+        &mut self,
+        requests: &[Request],
+        senders: &mut [AccountData],
+        target_tps: u64,
+        duration: time::Duration,
+    ) -> SustainedLoadSummary {
+        let mut submit_rate = target_tps;
+        // Outstanding TXNs this run has submitted but not yet observed as committed, used to
+        // estimate the commit backlog. Aged out independently of TTL-based caches elsewhere.
+        let mut outstanding: Vec<(AccountAddress, u64, time::Instant)> = vec![];
+        let mut total_submitted = 0usize;
+        let mut total_accepted = 0usize;
+        let mut total_committed = 0usize;
+        let start = time::Instant::now();
+        let mut offset = 0usize;
+        while start.elapsed() < duration && offset < requests.len() {
+            // Offer roughly one control tick's worth of requests at the current rate.
+            let tick_size = std::cmp::min(
+                requests.len() - offset,
+                std::cmp::max(1, (submit_rate / 10) as usize),
+            );
+            let tick_requests = &requests[offset..offset + tick_size];
+            offset += tick_size;
+
+            outstanding.retain(|(_, _, submitted_at)| {
+                submitted_at.elapsed().as_millis() < MAX_TX_QUEUE_AGE_MS
+            });
+
+            let submitted_at = time::Instant::now();
+            let (num_accepted, _) = self.submit_requests(tick_requests, submit_rate);
+            total_submitted += tick_requests.len();
+            total_accepted += num_accepted;
+            for request in tick_requests {
+                outstanding.push((request.sender, request.sequence_number, submitted_at));
+            }
+
+            // check_txn_results resets each sender's sequence_number down to the synced
+            // (committed-so-far) value and asserts sender.sequence_number >= sync_sequence_number
+            // on its way in. Unlike every other caller, this loop never advances senders'
+            // sequence numbers back up between ticks, so restore them here to reflect the
+            // requests just submitted this tick, or the very next tick's check_txn_results call
+            // panics as soon as the validator commits anything past tick 1.
+            let tick_targets = max_sequence_numbers(tick_requests);
+            for sender in senders.iter_mut() {
+                if let Some(target) = tick_targets.get(&sender.address) {
+                    sender.sequence_number = std::cmp::max(sender.sequence_number, *target + 1);
+                }
+            }
+
+            let (sync_sequence_numbers, _) = self.wait_txns_committed(senders);
+            let (num_committed, _, _) = self.check_txn_results(senders, &sync_sequence_numbers);
+            total_committed += num_committed;
+            outstanding.retain(|(address, sequence_number, _)| {
+                sync_sequence_numbers
+                    .get(address)
+                    .map_or(true, |synced_sequence_number| {
+                        sequence_number >= synced_sequence_number
+                    })
+            });
+
+            let backlog_secs = outstanding.len() as f64 / submit_rate.max(1) as f64;
+            let rejection_ratio = if total_submitted > 0 {
+                1.0 - (total_accepted as f64 / total_submitted as f64)
+            } else {
+                0.0
+            };
+            submit_rate = next_submit_rate(submit_rate, target_tps, backlog_secs, rejection_ratio);
+            debug!(
+                "Sustained load tick: submit_rate = {}, backlog = {:.2}s, rejection_ratio = {:.2}",
+                submit_rate, backlog_secs, rejection_ratio,
+            );
+        }
+        let elapsed_secs = start.elapsed().as_secs_f64();
+        let rejection_ratio = if total_submitted > 0 {
+            1.0 - (total_accepted as f64 / total_submitted as f64)
+        } else {
+            0.0
+        };
+        SustainedLoadSummary {
+            committed_tps: if elapsed_secs > 0.0 {
+                total_committed as f64 / elapsed_secs
+            } else {
+                0.0
+            },
+            converged_submit_rate: submit_rate,
+            rejection_ratio,
+        }
+    }
+}
+
+/// Each sender's highest sequence number among `requests`. One past it is the sender's
+/// locally expected sequence number, used to restore AccountData after `check_txn_results`
+/// resets it down to the synced (committed-so-far) value.
+fn max_sequence_numbers(requests: &[Request]) -> HashMap<AccountAddress, u64> {
+    requests.iter().fold(HashMap::new(), |mut targets, request| {
+        let target = targets.entry(request.sender).or_insert(request.sequence_number);
+        *target = std::cmp::max(*target, request.sequence_number);
+        targets
+    })
+}
+
+/// Pure step function for `run_sustained_load`'s rate controller: back off on backlog or
+/// rejections, ramp up once backlog has room, otherwise hold steady. Extracted out of the tick
+/// loop so the controller logic can be unit tested without a live AdmissionControlClient.
+fn next_submit_rate(submit_rate: u64, target_tps: u64, backlog_secs: f64, rejection_ratio: f64) -> u64 {
+    if backlog_secs > SUSTAINED_LOAD_BACKLOG_THRESHOLD_SECS
+        || rejection_ratio > SUSTAINED_LOAD_REJECTION_THRESHOLD
+    {
+        std::cmp::max(1, (submit_rate as f64 * SUSTAINED_LOAD_RATE_DOWN_STEP) as u64)
+    } else if backlog_secs < SUSTAINED_LOAD_BACKLOG_THRESHOLD_SECS / 2.0 {
+        std::cmp::min(
+            target_tps,
+            std::cmp::max(1, (submit_rate as f64 * SUSTAINED_LOAD_RATE_UP_STEP) as u64),
+        )
+    } else {
+        submit_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn next_submit_rate_backs_off_on_backlog() {
+        let rate = next_submit_rate(100, 200, SUSTAINED_LOAD_BACKLOG_THRESHOLD_SECS + 1.0, 0.0);
+        assert_eq!(rate, (100f64 * SUSTAINED_LOAD_RATE_DOWN_STEP) as u64);
+    }
+
+    #[test]
+    fn next_submit_rate_backs_off_on_rejections() {
+        let rate = next_submit_rate(100, 200, 0.0, SUSTAINED_LOAD_REJECTION_THRESHOLD + 0.01);
+        assert_eq!(rate, (100f64 * SUSTAINED_LOAD_RATE_DOWN_STEP) as u64);
+    }
+
+    #[test]
+    fn next_submit_rate_ramps_up_when_backlog_is_low() {
+        let rate = next_submit_rate(100, 200, 0.0, 0.0);
+        assert_eq!(rate, (100f64 * SUSTAINED_LOAD_RATE_UP_STEP) as u64);
+    }
+
+    #[test]
+    fn next_submit_rate_never_exceeds_target_tps() {
+        let rate = next_submit_rate(190, 200, 0.0, 0.0);
+        assert_eq!(rate, 200);
+    }
+
+    #[test]
+    fn next_submit_rate_holds_steady_in_the_middle_band() {
+        let backlog_secs = SUSTAINED_LOAD_BACKLOG_THRESHOLD_SECS * 0.75;
+        let rate = next_submit_rate(100, 200, backlog_secs, 0.0);
+        assert_eq!(rate, 100);
+    }
+
+    #[test]
+    fn next_submit_rate_never_drops_below_one() {
+        let rate = next_submit_rate(1, 200, SUSTAINED_LOAD_BACKLOG_THRESHOLD_SECS + 1.0, 0.0);
+        assert_eq!(rate, 1);
+    }
+
+    #[test]
+    fn max_sequence_numbers_tracks_the_highest_per_sender() {
+        let sender_a = AccountAddress::default();
+        let sender_b = association_address();
+        let requests = vec![
+            Request {
+                sender: sender_a,
+                sequence_number: 3,
+            },
+            Request {
+                sender: sender_a,
+                sequence_number: 5,
+            },
+            Request {
+                sender: sender_b,
+                sequence_number: 1,
+            },
+        ];
+        let targets = max_sequence_numbers(&requests);
+        assert_eq!(targets.get(&sender_a), Some(&5));
+        assert_eq!(targets.get(&sender_b), Some(&1));
+    }
+
+    #[test]
+    fn max_sequence_numbers_of_empty_requests_is_empty() {
+        assert!(max_sequence_numbers(&[]).is_empty());
+    }
+
+    fn bench_summary(num_submitted: usize, num_accepted: usize, num_committed: usize) -> BenchSummary {
+        BenchSummary {
+            num_submitted,
+            num_accepted,
+            num_committed,
+            submit_duration_ms: 1,
+            wait_duration_ms: 1,
+            max_txn_throughput: 0.0,
+            tps_samples: vec![],
+            latency_stats: LatencyStats::default(),
+            retry_attempts: 0,
+            committed_after_retry: 0,
+        }
+    }
+
+    #[test]
+    fn has_uncommitted_txns_does_not_underflow_when_retries_grow_both_counts() {
+        // Regression test: a retry round that resubmits requests never accepted in round 1
+        // must grow num_accepted alongside num_committed, or this subtraction underflows.
+        let summary = bench_summary(10, 10, 10);
+        assert!(!summary.has_uncommitted_txns());
+        assert!(!summary.has_rejected_txns());
+    }
+
+    #[test]
+    fn has_uncommitted_txns_is_true_when_committed_trails_accepted() {
+        let summary = bench_summary(10, 8, 5);
+        assert!(summary.has_uncommitted_txns());
+    }
+
+    #[test]
+    fn has_rejected_txns_is_true_when_accepted_trails_submitted() {
+        let summary = bench_summary(10, 8, 8);
+        assert!(summary.has_rejected_txns());
+        assert!(!summary.has_uncommitted_txns());
+    }
+
+    #[test]
+    fn accepted_keys_dedup_a_duplicate_accept_across_retry_rounds() {
+        // Regression test: the retry filter resubmits the entire outstanding gap, which can
+        // include a TXN that was already accepted (just still pending commit) in an earlier
+        // round. Tracking acceptance by (sender, sequence number) identity in a HashSet, as
+        // submit_requests_and_wait_txns_committed_with_retry now does, must not let a
+        // duplicate accept for the same key inflate the accepted count.
+        let sender = AccountAddress::default();
+        let mut accepted_keys: HashSet<(AccountAddress, u64)> = HashSet::new();
+        accepted_keys.insert((sender, 0));
+        accepted_keys.insert((sender, 1));
+        let num_submitted = 2;
+        // Retry round re-accepts the same (sender, 0) that round 1 already accepted.
+        let retry_accepted_keys: HashSet<(AccountAddress, u64)> =
+            [(sender, 0)].iter().cloned().collect();
+        accepted_keys.extend(retry_accepted_keys);
+        assert_eq!(accepted_keys.len(), num_submitted);
+    }
+
+    #[test]
+    fn chunks_leave_the_remainder_in_a_smaller_last_batch() {
+        let requests: Vec<u32> = (0..10).collect();
+        let batches: Vec<&[u32]> = requests.chunks(4).collect();
+        assert_eq!(batches.len(), 3);
+        assert_eq!(batches[0].len(), 4);
+        assert_eq!(batches[1].len(), 4);
+        assert_eq!(batches[2].len(), 2);
+    }
+
+    #[test]
+    fn batch_size_clamp_turns_zero_into_one() {
+        // Same clamp as with_batch_size applies; this does not call with_batch_size itself.
+        let batch_size = std::cmp::max(1, 0usize);
+        assert_eq!(batch_size, 1);
+    }
 }
@@ -0,0 +1,182 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Streams Benchmarker metrics to an optional InfluxDB HTTP endpoint so long runs can be
+//! graphed over time instead of only read once locally off `OP_COUNTER`.
+//!
+//! Mirrors Solana's bench-tps `metrics::submit(influxdb::Point::new(...))`: points are
+//! accumulated on a dedicated background thread and flushed to InfluxDB's line-protocol HTTP
+//! write API on a fixed interval, with remaining points drained on shutdown. When no endpoint
+//! is configured the sink is a no-op, so existing local-only behavior is unchanged.
+
+use logger::prelude::*;
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// One InfluxDB line-protocol point: a measurement name, its field values, and tags
+/// identifying which run/client produced it.
+#[derive(Clone, Debug)]
+pub struct MetricPoint {
+    pub measurement: String,
+    pub tags: Vec<(String, String)>,
+    pub fields: Vec<(String, f64)>,
+}
+
+impl MetricPoint {
+    pub fn new(measurement: &str, tags: Vec<(String, String)>, fields: Vec<(String, f64)>) -> Self {
+        MetricPoint {
+            measurement: measurement.to_string(),
+            tags,
+            fields,
+        }
+    }
+
+    fn to_line_protocol(&self) -> String {
+        let tags: String = self
+            .tags
+            .iter()
+            .map(|(key, value)| format!(",{}={}", key, value))
+            .collect();
+        let fields = self
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(",");
+        format!("{}{} {}", self.measurement, tags, fields)
+    }
+}
+
+/// Background sink flushing accumulated `MetricPoint`s to an InfluxDB HTTP write endpoint at a
+/// fixed interval. A no-op (nothing is spawned) when no endpoint is configured.
+pub struct InfluxSink {
+    sender: Option<Sender<MetricPoint>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl InfluxSink {
+    /// Spawn a flush thread posting to `{endpoint}/write?db={database}` every
+    /// `flush_interval`. Returns a sink that silently drops submitted points if `endpoint`
+    /// is `None`.
+    pub fn new(endpoint: Option<String>, database: String, flush_interval: Duration) -> Self {
+        let endpoint = match endpoint {
+            Some(endpoint) => endpoint,
+            None => {
+                return InfluxSink {
+                    sender: None,
+                    handle: None,
+                };
+            }
+        };
+        let (sender, receiver): (Sender<MetricPoint>, Receiver<MetricPoint>) = mpsc::channel();
+        let write_url = format!("{}/write?db={}", endpoint, database);
+        let handle = thread::spawn(move || {
+            let client = reqwest::blocking::Client::new();
+            let mut pending = vec![];
+            let mut last_flush = Instant::now();
+            loop {
+                match receiver.recv_timeout(flush_interval) {
+                    Ok(point) => pending.push(point),
+                    Err(mpsc::RecvTimeoutError::Timeout) => {}
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        Self::flush(&client, &write_url, &mut pending);
+                        break;
+                    }
+                }
+                if !pending.is_empty() && last_flush.elapsed() >= flush_interval {
+                    Self::flush(&client, &write_url, &mut pending);
+                    last_flush = Instant::now();
+                }
+            }
+        });
+        InfluxSink {
+            sender: Some(sender),
+            handle: Some(handle),
+        }
+    }
+
+    /// Enqueue one point to be flushed on the next tick. No-op if no endpoint was configured.
+    pub fn submit(&self, point: MetricPoint) {
+        if let Some(sender) = &self.sender {
+            if sender.send(point).is_err() {
+                error!("Metrics sink flush thread is gone; dropping point");
+            }
+        }
+    }
+
+    fn flush(client: &reqwest::blocking::Client, write_url: &str, pending: &mut Vec<MetricPoint>) {
+        if pending.is_empty() {
+            return;
+        }
+        let body = pending
+            .iter()
+            .map(MetricPoint::to_line_protocol)
+            .collect::<Vec<_>>()
+            .join("\n");
+        if let Err(e) = client.post(write_url).body(body).send() {
+            error!(
+                "Failed to flush {} metric point(s) to InfluxDB: {:?}",
+                pending.len(),
+                e
+            );
+        }
+        pending.clear();
+    }
+}
+
+impl Drop for InfluxSink {
+    /// Drop the sender so the flush thread observes `Disconnected`, flushes remaining points,
+    /// and exits; then join it so points aren't lost on shutdown.
+    fn drop(&mut self) {
+        self.sender.take();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn to_line_protocol_formats_measurement_tags_and_fields() {
+        let point = MetricPoint::new(
+            "benchmark",
+            vec![("run_id".to_string(), "abc".to_string())],
+            vec![("txn_throughput".to_string(), 123.0)],
+        );
+        assert_eq!(
+            point.to_line_protocol(),
+            "benchmark,run_id=abc txn_throughput=123"
+        );
+    }
+
+    #[test]
+    fn to_line_protocol_supports_multiple_tags_and_fields() {
+        let point = MetricPoint::new(
+            "benchmark",
+            vec![
+                ("run_id".to_string(), "abc".to_string()),
+                ("num_clients".to_string(), "4".to_string()),
+            ],
+            vec![
+                ("request_throughput".to_string(), 1.5),
+                ("txn_throughput".to_string(), 2.5),
+            ],
+        );
+        assert_eq!(
+            point.to_line_protocol(),
+            "benchmark,run_id=abc,num_clients=4 request_throughput=1.5,txn_throughput=2.5"
+        );
+    }
+
+    #[test]
+    fn to_line_protocol_with_no_tags_omits_leading_comma() {
+        let point = MetricPoint::new("benchmark", vec![], vec![("value".to_string(), 1.0)]);
+        assert_eq!(point.to_line_protocol(), "benchmark value=1");
+    }
+}
@@ -0,0 +1,197 @@
+// Copyright (c) The Libra Core Contributors
+// SPDX-License-Identifier: Apache-2.0
+
+//! Continuous TPS sampling.
+//!
+//! `Benchmarker` normally derives throughput from a single before/after sequence number
+//! comparison, which hides ramp-up behavior and steady-state peaks behind one flat average.
+//! `TpsSampler` instead polls a validator's latest committed ledger version on a background
+//! thread at a fixed cadence while requests are being submitted and waited on, so a run can
+//! report a max and a time-weighted mean TPS instead of a single number.
+
+use admission_control_proto::proto::admission_control::{
+    AdmissionControlClient, UpdateToLatestLedgerRequest,
+};
+use logger::prelude::*;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Floor applied to the elapsed time between two samples before computing an incremental TPS,
+/// so a sampler thread that gets rescheduled back-to-back can't produce a division spike.
+const MIN_SAMPLE_DT_MS: f64 = 1.0;
+
+/// One (time, committed ledger version) observation taken by a sampler thread.
+#[derive(Clone, Copy, Debug)]
+pub struct TpsSample {
+    pub instant: Instant,
+    pub version: u64,
+}
+
+/// Max and time-weighted mean TPS derived from a sequence of `TpsSample`s.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TpsSamplerStats {
+    /// Highest incremental TPS observed between any two consecutive samples.
+    pub max_tps: f64,
+    /// Time-weighted mean TPS across the intervals in which the version advanced; intervals
+    /// with no new commits are left out of both the sum and the total time.
+    pub mean_tps: f64,
+}
+
+/// Polls one `AdmissionControlClient` for its latest committed ledger version every
+/// `poll_interval_ms`, on a background thread, until `stop_and_join` is called.
+pub struct TpsSampler {
+    stop: Arc<AtomicBool>,
+    handle: thread::JoinHandle<Vec<TpsSample>>,
+}
+
+impl TpsSampler {
+    /// Spawn a background thread sampling `client`'s committed ledger version every
+    /// `poll_interval_ms`.
+    pub fn spawn(client: Arc<AdmissionControlClient>, poll_interval_ms: u64) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread_stop = Arc::clone(&stop);
+        let handle = thread::spawn(move || -> Vec<TpsSample> {
+            let mut samples = vec![];
+            // The very first sample only establishes a baseline version/time; it never
+            // contributes an incremental TPS on its own. Skipped entirely on a failed poll,
+            // same as every later sample, so a transient error here can't masquerade as a
+            // baseline version of 0.
+            if let Some(version) = Self::query_committed_version(&client) {
+                samples.push(TpsSample {
+                    instant: Instant::now(),
+                    version,
+                });
+            }
+            while !thread_stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(poll_interval_ms));
+                if let Some(version) = Self::query_committed_version(&client) {
+                    samples.push(TpsSample {
+                        instant: Instant::now(),
+                        version,
+                    });
+                }
+            }
+            samples
+        });
+        TpsSampler { stop, handle }
+    }
+
+    /// Signal the sampler thread to stop, join it, and return the raw samples collected.
+    pub fn stop_and_join(self) -> Vec<TpsSample> {
+        self.stop.store(true, Ordering::Relaxed);
+        self.handle
+            .join()
+            .expect("failed to join TPS sampler thread")
+    }
+
+    /// Query the validator's current committed ledger version via `UpdateToLatestLedger`.
+    /// Returns `None` on a gRPC error so the caller can skip the sample entirely, rather than
+    /// synthesizing a version of 0 that would fabricate a spurious TPS spike on the next window.
+    fn query_committed_version(client: &AdmissionControlClient) -> Option<u64> {
+        let req = UpdateToLatestLedgerRequest::new();
+        match client.update_to_latest_ledger(&req) {
+            Ok(resp) => Some(resp.get_ledger_info_with_sigs().get_ledger_info().version),
+            Err(e) => {
+                error!("Failed to poll ledger version for TPS sampling: {:?}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Reduce a sequence of samples into max and time-weighted mean incremental TPS.
+/// Intervals where the version didn't advance (the validator hasn't committed anything new
+/// between two polls) are discarded rather than counted as 0 TPS.
+pub fn compute_stats(samples: &[TpsSample]) -> TpsSamplerStats {
+    let mut max_tps = 0f64;
+    let mut weighted_sum_tps = 0f64;
+    let mut total_dt_ms = 0f64;
+    for window in samples.windows(2) {
+        let (prev, curr) = (&window[0], &window[1]);
+        if curr.version <= prev.version {
+            continue;
+        }
+        let dt_ms = (curr.instant.duration_since(prev.instant).as_millis() as f64)
+            .max(MIN_SAMPLE_DT_MS);
+        let tps = (curr.version - prev.version) as f64 * 1000f64 / dt_ms;
+        max_tps = max_tps.max(tps);
+        weighted_sum_tps += tps * dt_ms;
+        total_dt_ms += dt_ms;
+    }
+    let mean_tps = if total_dt_ms > 0f64 {
+        weighted_sum_tps / total_dt_ms
+    } else {
+        0f64
+    };
+    TpsSamplerStats { max_tps, mean_tps }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_at(offset_ms: u64, version: u64, base: Instant) -> TpsSample {
+        TpsSample {
+            instant: base + Duration::from_millis(offset_ms),
+            version,
+        }
+    }
+
+    #[test]
+    fn empty_samples_yield_zero_stats() {
+        let stats = compute_stats(&[]);
+        assert_eq!(stats.max_tps, 0f64);
+        assert_eq!(stats.mean_tps, 0f64);
+    }
+
+    #[test]
+    fn single_sample_yields_zero_stats() {
+        let base = Instant::now();
+        let stats = compute_stats(&[sample_at(0, 100, base)]);
+        assert_eq!(stats.max_tps, 0f64);
+        assert_eq!(stats.mean_tps, 0f64);
+    }
+
+    #[test]
+    fn unchanged_version_intervals_are_discarded() {
+        let base = Instant::now();
+        let samples = vec![
+            sample_at(0, 100, base),
+            sample_at(100, 100, base),
+            sample_at(200, 200, base),
+        ];
+        let stats = compute_stats(&samples);
+        // Only the second interval (100 -> 200 over 100ms = 1000 tps) should count.
+        assert!((stats.max_tps - 1000f64).abs() < 1e-9);
+        assert!((stats.mean_tps - 1000f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn back_to_back_samples_are_clamped_to_min_dt() {
+        let base = Instant::now();
+        let samples = vec![sample_at(0, 100, base), sample_at(0, 110, base)];
+        let stats = compute_stats(&samples);
+        // dt clamped to MIN_SAMPLE_DT_MS = 1ms, so 10 versions / 1ms = 10_000 tps.
+        assert!((stats.max_tps - 10_000f64).abs() < 1e-9);
+    }
+
+    #[test]
+    fn max_tps_tracks_the_fastest_interval() {
+        let base = Instant::now();
+        let samples = vec![
+            sample_at(0, 0, base),
+            sample_at(1000, 100, base),
+            sample_at(1100, 200, base),
+        ];
+        let stats = compute_stats(&samples);
+        // First interval: 100 versions / 1000ms = 100 tps.
+        // Second interval: 100 versions / 100ms = 1000 tps.
+        assert!((stats.max_tps - 1000f64).abs() < 1e-9);
+    }
+}